@@ -1,18 +1,90 @@
 use anyhow::anyhow;
+use crossbeam::channel::bounded;
 use crossbeam::channel::unbounded;
 use crossbeam::channel::Receiver;
 use crossbeam::channel::Sender;
+use regex::Regex;
+use serde::ser::SerializeSeq;
+use serde::Serializer as _;
 use std::collections::HashMap;
+use std::io::BufWriter;
 use std::process::Command;
 use std::sync::Arc;
 use std::thread;
 
+/// Upper bound on the number of worker threads we'll spawn even if the
+/// machine reports a very large number of CPUs or the user asks for more
+/// via `--jobs`.
+const MAX_JOBS: usize = 32;
+
+/// Floor and ceiling, in milliseconds, for the decorrelated-jitter backoff
+/// `ToolOp::get_item` uses between retries.
+const BACKOFF_BASE_MS: u64 = 1000;
+const BACKOFF_CAP_MS: u64 = 60_000;
+
+/// Picks a default worker count from the number of available CPUs (1 if that
+/// can't be determined), capped at `MAX_JOBS` so we don't spawn an
+/// unreasonable number of `op` subprocesses.
+fn default_jobs() -> usize {
+    let cpus = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+
+    cpus.clamp(1, MAX_JOBS)
+}
+
 #[derive(Eq, PartialEq, Debug)]
 struct Item {
     id: String,
     json: serde_json::Value,
 }
 
+/// Scopes which items from `items list` get fetched, evaluated against the
+/// JSON metadata 'op' already returns for each item (before we've spent a
+/// separate 'items get' call on it).
+#[derive(Default)]
+struct ItemFilter {
+    /// Matched against the item's title.
+    title: Option<Regex>,
+    category: Option<String>,
+    /// Matched against either the vault's id or its name.
+    vault: Option<String>,
+}
+
+impl ItemFilter {
+    fn matches(&self, item: &serde_json::Value) -> bool {
+        if let Some(title) = &self.title {
+            let item_title = item.get("title").and_then(|v| v.as_str()).unwrap_or("");
+            if !title.is_match(item_title) {
+                return false;
+            }
+        }
+
+        if let Some(category) = &self.category {
+            let item_category = item.get("category").and_then(|v| v.as_str()).unwrap_or("");
+            if item_category != category {
+                return false;
+            }
+        }
+
+        if let Some(vault) = &self.vault {
+            let matches_vault = match item.get("vault") {
+                Some(serde_json::Value::Object(v)) => {
+                    v.get("id").and_then(|v| v.as_str()) == Some(vault.as_str())
+                        || v.get("name").and_then(|v| v.as_str()) == Some(vault.as_str())
+                }
+                Some(serde_json::Value::String(s)) => s == vault,
+                _ => false,
+            };
+            if !matches_vault {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
 fn check_output(cmd: &mut Command) -> anyhow::Result<String> {
     let output = cmd.output()?;
     if !output.status.success() {
@@ -31,8 +103,10 @@ fn check_output(cmd: &mut Command) -> anyhow::Result<String> {
 ///
 /// See also: https://1password.com/downloads/command-line/
 trait Op: Send + Sync + 'static {
-    /// Returns a Vec of ids of items.
-    fn list_items(&self) -> anyhow::Result<Vec<String>>;
+    /// Returns the JSON objects describing each item, as reported by
+    /// 'items list' (id, title, category, vault, etc.) so callers can filter
+    /// on that metadata before fetching.
+    fn list_items(&self) -> anyhow::Result<Vec<serde_json::Value>>;
     fn get_item(&self, id: &str) -> anyhow::Result<serde_json::Value>;
 }
 
@@ -48,8 +122,12 @@ struct MockOp {
 }
 
 impl Op for MockOp {
-    fn list_items(&self) -> anyhow::Result<Vec<String>> {
-        Ok(self.items.keys().map(|s| s.to_owned()).collect())
+    fn list_items(&self) -> anyhow::Result<Vec<serde_json::Value>> {
+        Ok(self
+            .items
+            .keys()
+            .map(|id| serde_json::json!({ "id": id }))
+            .collect())
     }
 
     fn get_item(&self, id: &str) -> anyhow::Result<serde_json::Value> {
@@ -101,7 +179,7 @@ fn parsed_as_json(s: anyhow::Result<String>) -> anyhow::Result<serde_json::Value
 }
 
 impl Op for ToolOp {
-    fn list_items(&self) -> anyhow::Result<Vec<String>> {
+    fn list_items(&self) -> anyhow::Result<Vec<serde_json::Value>> {
         let output = check_output(
             Command::new("/usr/bin/env")
                 .arg(self.command.clone())
@@ -111,34 +189,24 @@ impl Op for ToolOp {
         )?;
         let json = serde_json::from_str(&output)?;
 
-        let items = match json {
+        match json {
             serde_json::Value::Array(items) => Ok(items),
             _ => Err(anyhow!(
                 "expected JSON list from 'items list', received something else"
             )),
-        }?;
-
-        items
-            .iter()
-            .map(|item| match item {
-                serde_json::Value::Object(obj) => {
-                    let id = obj.get("id");
-                    match id {
-                        Some(id) => match id {
-                            serde_json::Value::String(id) => Ok(id.into()),
-                            _ => Err(anyhow!("item's id key's value is not a string")),
-                        },
-                        None => Err(anyhow!("item has no id key")),
-                    }
-                }
-                _ => Err(anyhow!("item is not an object")),
-            })
-            .collect()
+        }
     }
 
     fn get_item(&self, id: &str) -> anyhow::Result<serde_json::Value> {
         let mut tries = 0;
 
+        // Decorrelated jitter (https://aws.amazon.com/blogs/architecture/exponential-backoff-and-jitter/):
+        // each retry sleeps a random amount between `BACKOFF_BASE_MS` and 3x the
+        // previous sleep, capped at `BACKOFF_CAP_MS`. This spreads out retries
+        // from multiple worker threads far better than sleeping a fixed,
+        // linearly-growing amount.
+        let mut sleep_ms = BACKOFF_BASE_MS;
+
         loop {
             tries += 1;
 
@@ -158,14 +226,14 @@ impl Op for ToolOp {
                     }
 
                     use rand::Rng;
-                    let backoff_time =
-                        rand::thread_rng().gen_range(tries * 3000, (tries + 1) * 3000);
+                    sleep_ms = BACKOFF_CAP_MS
+                        .min(rand::thread_rng().gen_range(BACKOFF_BASE_MS, sleep_ms * 3));
 
                     if self.backoff {
-                        println!("get item: backing off: {}ms", backoff_time);
-                        std::thread::sleep(std::time::Duration::from_millis(backoff_time));
+                        println!("get item: backing off: {}ms", sleep_ms);
+                        std::thread::sleep(std::time::Duration::from_millis(sleep_ms));
                     } else {
-                        println!("get item: would have backed off: {}ms", backoff_time);
+                        println!("get item: would have backed off: {}ms", sleep_ms);
                     }
                 }
             }
@@ -173,51 +241,142 @@ impl Op for ToolOp {
     }
 }
 
+/// Controls whether `ProgressReporter` prints free text or newline-delimited
+/// JSON events to stderr, so op-export can be wrapped by other tooling.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ReporterMode {
+    Pretty,
+    Json,
+}
+
+impl std::str::FromStr for ReporterMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<ReporterMode> {
+        match s {
+            "pretty" => Ok(ReporterMode::Pretty),
+            "json" => Ok(ReporterMode::Json),
+            _ => Err(anyhow!(
+                "unknown reporter '{}' (want 'pretty' or 'json')",
+                s
+            )),
+        }
+    }
+}
+
 struct ProgressReporter {
+    mode: ReporterMode,
     last_report: std::time::Instant,
     num_pending: usize,
 }
 
 impl ProgressReporter {
-    fn new() -> ProgressReporter {
+    fn new(mode: ReporterMode) -> ProgressReporter {
         ProgressReporter {
+            mode,
             last_report: std::time::Instant::now(),
             num_pending: 0,
         }
     }
+
+    fn listing(&self, total: usize) {
+        match self.mode {
+            ReporterMode::Pretty => {
+                eprintln!("{} items match filters - initiating fetch", total)
+            }
+            ReporterMode::Json => {
+                eprintln!(
+                    "{}",
+                    serde_json::json!({"event": "listing", "total": total})
+                )
+            }
+        }
+    }
+
     fn pending(&mut self) {
         self.num_pending += 1;
     }
 
-    fn done(&mut self) {
+    fn fetched(&mut self, id: &str) {
         self.num_pending -= 1;
 
-        if self.num_pending > 0 {
-            let now = std::time::Instant::now();
-            if now.duration_since(self.last_report) > std::time::Duration::from_millis(1000) {
-                self.last_report = now;
-                eprintln!("{} items still to go", self.num_pending);
+        match self.mode {
+            ReporterMode::Pretty => {
+                let now = std::time::Instant::now();
+                if self.num_pending > 0
+                    && now.duration_since(self.last_report) > std::time::Duration::from_millis(1000)
+                {
+                    self.last_report = now;
+                    eprintln!("{} items still to go", self.num_pending);
+                }
             }
+            ReporterMode::Json => eprintln!(
+                "{}",
+                serde_json::json!({"event": "fetched", "id": id, "pending": self.num_pending})
+            ),
+        }
+    }
+
+    fn done(&self, written: usize, failed: usize) {
+        if self.mode == ReporterMode::Json {
+            eprintln!(
+                "{}",
+                serde_json::json!({"event": "done", "written": written, "failed": failed})
+            );
         }
     }
 }
 
-fn get_items(r: Receiver<String>, s: Sender<anyhow::Result<Item>>, op: Arc<dyn Op>) {
+fn get_items(
+    r: Receiver<String>,
+    s: Sender<(String, anyhow::Result<serde_json::Value>)>,
+    op: Arc<dyn Op>,
+) {
     for id in r {
-        if s.send(op.get_item(&id).map(|json| Item { id, json }))
-            .is_err()
-        {
+        let result = op.get_item(&id);
+        if s.send((id, result)).is_err() {
             break;
         }
     }
 }
 
-fn fetch_all_items(op: Arc<dyn Op>) -> anyhow::Result<Vec<Item>> {
-    let (id_sender, id_receiver) = unbounded::<String>();
-    let (item_sender, item_receiver) = unbounded::<anyhow::Result<Item>>();
+/// Outcome of a `--keep-going` fetch: the items that were fetched
+/// successfully, plus the id and error string of each one that wasn't.
+struct FetchResult {
+    items: Vec<Item>,
+    failures: Vec<(String, String)>,
+}
+
+/// Handles produced by `spawn_fetch`: the channel fetched items arrive on,
+/// the threads (fetch workers and the id feeder) to join once that channel
+/// is drained, and a progress
+/// reporter already primed with the total item count.
+struct FetchHandles {
+    items: Receiver<(String, anyhow::Result<serde_json::Value>)>,
+    threads: Vec<std::thread::JoinHandle<()>>,
+    progress: ProgressReporter,
+}
+
+/// Lists, filters, and optionally shuffles the ids to export, spawns `jobs`
+/// `get_items` workers to fetch them, and hands back the resulting
+/// `FetchHandles`.
+fn spawn_fetch(
+    op: Arc<dyn Op>,
+    jobs: usize,
+    shuffle: bool,
+    seed: Option<u64>,
+    filter: &ItemFilter,
+    reporter_mode: ReporterMode,
+) -> anyhow::Result<FetchHandles> {
+    // Bounded so that listing can't race arbitrarily far ahead of fetching -
+    // once `jobs` workers plus a small buffer of ids are queued up, sending
+    // more ids blocks until a worker drains one.
+    let (id_sender, id_receiver) = bounded::<String>(jobs * 4);
+    let (item_sender, item_receiver) =
+        bounded::<(String, anyhow::Result<serde_json::Value>)>(jobs);
 
     let mut bgthreads: Vec<std::thread::JoinHandle<()>> = vec![];
-    for _ in 0..2 {
+    for _ in 0..jobs {
         let opclone = op.clone();
         let rcvclone = id_receiver.clone();
         let sndclone = item_sender.clone();
@@ -227,29 +386,110 @@ fn fetch_all_items(op: Arc<dyn Op>) -> anyhow::Result<Vec<Item>> {
     }
     drop(item_sender);
 
-    eprintln!("listing items to export");
-    let item_ids = op.list_items()?;
+    if reporter_mode == ReporterMode::Pretty {
+        eprintln!("listing items to export");
+    }
+    let items = op.list_items()?;
+
+    let mut item_ids: Vec<String> = items
+        .iter()
+        .filter(|item| filter.matches(item))
+        .map(id_of_item)
+        .collect::<anyhow::Result<_>>()?;
+
+    let mut progress = ProgressReporter::new(reporter_mode);
+    progress.listing(item_ids.len());
+
+    if shuffle {
+        use rand::rngs::StdRng;
+        use rand::seq::SliceRandom;
+        use rand::SeedableRng;
+
+        let seed = seed.unwrap_or_else(|| {
+            let seed = rand::random();
+            match reporter_mode {
+                ReporterMode::Pretty => {
+                    eprintln!("no --seed given, using random seed {}", seed)
+                }
+                ReporterMode::Json => {
+                    eprintln!("{}", serde_json::json!({"event": "seed", "value": seed}))
+                }
+            }
+            seed
+        });
 
-    eprintln!("{} total items - initiating fetch", item_ids.len());
+        let mut rng = StdRng::seed_from_u64(seed);
+        item_ids.shuffle(&mut rng);
+    }
 
-    let mut progress = ProgressReporter::new();
-    for id in op.list_items()? {
+    for _ in &item_ids {
         progress.pending();
-        id_sender.send(id)?;
     }
-    drop(id_sender);
 
-    // Note: This pipeline will shortcircuit during collect() if an error is encountered,
-    // thus closing the underlying channel since item_receiver will be consumed.
-    //
-    // Not sure how to make this more explicit while still being idiomatic?
-    let items: anyhow::Result<Vec<Item>> = item_receiver
-        .into_iter()
-        .map(|it| {
-            progress.done();
-            it
+    // Ids are fed from their own thread so the caller can start draining
+    // fetched items right away; with both channels bounded, feeding them
+    // from this thread would stall once the item channel filled up.
+    bgthreads.push(thread::spawn(move || {
+        for id in item_ids {
+            if id_sender.send(id).is_err() {
+                break;
+            }
+        }
+    }));
+
+    Ok(FetchHandles {
+        items: item_receiver,
+        threads: bgthreads,
+        progress,
+    })
+}
+
+fn fetch_all_items(
+    op: Arc<dyn Op>,
+    jobs: usize,
+    shuffle: bool,
+    seed: Option<u64>,
+    filter: &ItemFilter,
+    keep_going: bool,
+    reporter_mode: ReporterMode,
+) -> anyhow::Result<FetchResult> {
+    let FetchHandles {
+        items: item_receiver,
+        threads: bgthreads,
+        mut progress,
+    } = spawn_fetch(op, jobs, shuffle, seed, filter, reporter_mode)?;
+
+    let result = if keep_going {
+        let mut items = Vec::new();
+        let mut failures = Vec::new();
+
+        for (id, result) in item_receiver {
+            progress.fetched(&id);
+            match result {
+                Ok(json) => items.push(Item { id, json }),
+                Err(e) => failures.push((id, e.to_string())),
+            }
+        }
+
+        Ok(FetchResult { items, failures })
+    } else {
+        // Note: This pipeline will shortcircuit during collect() if an error is encountered,
+        // thus closing the underlying channel since item_receiver will be consumed.
+        //
+        // Not sure how to make this more explicit while still being idiomatic?
+        let items: anyhow::Result<Vec<Item>> = item_receiver
+            .into_iter()
+            .map(|(id, result)| {
+                progress.fetched(&id);
+                result.map(|json| Item { id, json })
+            })
+            .collect();
+
+        items.map(|items| FetchResult {
+            items,
+            failures: vec![],
         })
-        .collect();
+    };
 
     for thread in bgthreads {
         match thread.join() {
@@ -260,7 +500,11 @@ fn fetch_all_items(op: Arc<dyn Op>) -> anyhow::Result<Vec<Item>> {
         }
     }
 
-    items
+    if let Ok(result) = &result {
+        progress.done(result.items.len(), result.failures.len());
+    }
+
+    result
 }
 
 fn id_of_item(item: &serde_json::Value) -> anyhow::Result<String> {
@@ -276,26 +520,191 @@ fn id_of_item(item: &serde_json::Value) -> anyhow::Result<String> {
     }
 }
 
-fn export(op_path: &str, dest_path: &str) -> anyhow::Result<()> {
-    let tool = ToolOp::new(op_path.to_owned());
-    let mut items = fetch_all_items(Arc::new(tool))?;
+/// With `--keep-going`, writes a JSON manifest of failed ids and errors to
+/// `failures_path` (if given). A no-op otherwise.
+fn write_failures_manifest(
+    keep_going: bool,
+    failures_path: Option<&str>,
+    failures: &[(String, String)],
+    reporter_mode: ReporterMode,
+) -> anyhow::Result<()> {
+    if !keep_going {
+        return Ok(());
+    }
 
-    items.sort_by_key(|item| id_of_item(&item.json).unwrap());
+    if let Some(failures_path) = failures_path {
+        let manifest: Vec<serde_json::Value> = failures
+            .iter()
+            .map(|(id, error)| serde_json::json!({ "id": id, "error": error }))
+            .collect();
+        std::fs::write(
+            failures_path,
+            serde_json::to_string_pretty(&serde_json::Value::Array(manifest))?,
+        )?;
+        if reporter_mode == ReporterMode::Pretty {
+            eprintln!("failure manifest written to {}", failures_path);
+        }
+    }
 
-    let json = serde_json::Value::Array(items.into_iter().map(|it| it.json).collect());
-    let pretty_printed = serde_json::to_string_pretty(&json)?;
+    Ok(())
+}
 
-    std::fs::write(dest_path, pretty_printed)?;
+/// Bundles the CLI-derived settings `export` needs. Kept as a struct rather
+/// than a growing list of positional arguments, since export has picked up
+/// another mode or flag in nearly every release.
+struct ExportOptions<'a> {
+    op_path: &'a str,
+    dest_path: &'a str,
+    jobs: usize,
+    shuffle: bool,
+    seed: Option<u64>,
+    filter: &'a ItemFilter,
+    keep_going: bool,
+    failures_path: Option<&'a str>,
+    reporter_mode: ReporterMode,
+    sorted: bool,
+}
 
-    eprintln!("items written to {} (sorted by id)", dest_path);
+fn export(opts: &ExportOptions) -> anyhow::Result<()> {
+    let op: Arc<dyn Op> = Arc::new(ToolOp::new(opts.op_path.to_owned()));
+    export_from(op, opts)
+}
 
-    Ok(())
+/// The guts of `export`, taking an `Op` directly rather than building a
+/// `ToolOp` from `opts.op_path`, so tests can drive it with a `MockOp`
+/// instead of spawning real subprocesses.
+fn export_from(op: Arc<dyn Op>, opts: &ExportOptions) -> anyhow::Result<()> {
+    if opts.sorted {
+        let result = fetch_all_items(
+            op,
+            opts.jobs,
+            opts.shuffle,
+            opts.seed,
+            opts.filter,
+            opts.keep_going,
+            opts.reporter_mode,
+        )?;
+        let mut items = result.items;
+
+        items.sort_by_key(|item| id_of_item(&item.json).unwrap());
+        let succeeded = items.len();
+
+        let json = serde_json::Value::Array(items.into_iter().map(|it| it.json).collect());
+        let pretty_printed = serde_json::to_string_pretty(&json)?;
+
+        std::fs::write(opts.dest_path, pretty_printed)?;
+
+        if opts.reporter_mode == ReporterMode::Pretty {
+            eprintln!("items written to {} (sorted by id)", opts.dest_path);
+
+            if opts.keep_going {
+                eprintln!("{} succeeded, {} failed", succeeded, result.failures.len());
+            }
+        }
+
+        return write_failures_manifest(
+            opts.keep_going,
+            opts.failures_path,
+            &result.failures,
+            opts.reporter_mode,
+        );
+    }
+
+    // Streaming path: items are serialized one at a time as they arrive from
+    // `item_receiver` instead of being buffered into a `Vec<Item>` first, so
+    // memory stays bounded by `jobs` rather than the size of the vault. This
+    // can't sort the output, which is why `--sorted` takes the buffered path
+    // above instead.
+    let FetchHandles {
+        items: item_receiver,
+        threads: bgthreads,
+        mut progress,
+    } = spawn_fetch(
+        op,
+        opts.jobs,
+        opts.shuffle,
+        opts.seed,
+        opts.filter,
+        opts.reporter_mode,
+    )?;
+
+    // Write to a sibling temp path and only rename it over `dest_path` once
+    // we know the export succeeded, so a failed (non-`--keep-going`) export
+    // leaves the destination untouched instead of a plausible-looking but
+    // incomplete file.
+    let tmp_path = format!("{}.tmp", opts.dest_path);
+    let file = std::fs::File::create(&tmp_path)?;
+    let mut ser = serde_json::Serializer::pretty(BufWriter::new(file));
+    let mut seq = ser.serialize_seq(None)?;
+
+    let mut written = 0;
+    let mut failures = Vec::new();
+    let mut first_error = None;
+
+    for (id, result) in item_receiver {
+        progress.fetched(&id);
+        match result {
+            Ok(json) => {
+                seq.serialize_element(&json)?;
+                written += 1;
+            }
+            Err(e) => {
+                if opts.keep_going {
+                    failures.push((id, e.to_string()));
+                } else {
+                    first_error = Some(e);
+                    break;
+                }
+            }
+        }
+    }
+
+    if first_error.is_none() {
+        seq.end()?;
+        // Flush now, so a write error surfaces before the rename below
+        // rather than when the writer is dropped after it.
+        use std::io::Write as _;
+        ser.into_inner().flush()?;
+    }
+
+    for thread in bgthreads {
+        match thread.join() {
+            Ok(_) => (),
+            Err(e) => return Err(anyhow!("thread died: {:?}", e)),
+        }
+    }
+
+    if let Some(e) = first_error {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    std::fs::rename(&tmp_path, opts.dest_path)?;
+
+    progress.done(written, failures.len());
+
+    if opts.reporter_mode == ReporterMode::Pretty {
+        eprintln!("{} items written to {} (streamed)", written, opts.dest_path);
+
+        if opts.keep_going {
+            eprintln!("{} succeeded, {} failed", written, failures.len());
+        }
+    }
+
+    write_failures_manifest(
+        opts.keep_going,
+        opts.failures_path,
+        &failures,
+        opts.reporter_mode,
+    )
 }
 
 fn main() -> anyhow::Result<()> {
     use clap::App;
     use clap::Arg;
 
+    let default_jobs = default_jobs().to_string();
+
     let matches = App::new("op-export")
         .arg(
             Arg::with_name("op")
@@ -313,12 +722,119 @@ fn main() -> anyhow::Result<()> {
                 .required(true)
                 .help("The path to which to write the export in JSON format (required)."),
         )
+        .arg(
+            Arg::with_name("jobs")
+                .short("j")
+                .long("jobs")
+                .value_name("N")
+                .takes_value(true)
+                .default_value(&default_jobs)
+                .help("Number of concurrent 'op items get' workers to run."),
+        )
+        .arg(
+            Arg::with_name("shuffle").long("shuffle").help(
+                "Randomize the order ids are fetched in, to help reproduce rate-limit failures.",
+            ),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .value_name("N")
+                .takes_value(true)
+                .requires("shuffle")
+                .help("Seed for --shuffle (default: a random seed, printed to stderr)."),
+        )
+        .arg(
+            Arg::with_name("filter")
+                .long("filter")
+                .value_name("REGEX")
+                .takes_value(true)
+                .help("Only export items whose title matches this regex."),
+        )
+        .arg(
+            Arg::with_name("category")
+                .long("category")
+                .value_name("CATEGORY")
+                .takes_value(true)
+                .help("Only export items in this category (e.g. LOGIN, PASSWORD)."),
+        )
+        .arg(
+            Arg::with_name("vault")
+                .long("vault")
+                .value_name("VAULT")
+                .takes_value(true)
+                .help("Only export items from this vault (matched by id or name)."),
+        )
+        .arg(Arg::with_name("keep-going").long("keep-going").help(
+            "Don't abort the export if some items fail to fetch; write the rest and report \
+                 a summary of the failures instead.",
+        ))
+        .arg(
+            Arg::with_name("failures")
+                .long("failures")
+                .value_name("PATH")
+                .takes_value(true)
+                .requires("keep-going")
+                .help("With --keep-going, write a JSON manifest of failed ids and errors here."),
+        )
+        .arg(
+            Arg::with_name("reporter")
+                .long("reporter")
+                .value_name("pretty|json")
+                .takes_value(true)
+                .default_value("pretty")
+                .help("How to report progress on stderr: free text, or newline-delimited JSON events."),
+        )
+        .arg(Arg::with_name("sorted").long("sorted").help(
+            "Buffer the whole export in memory and sort it by id, instead of streaming items \
+                 to disk as they're fetched.",
+        ))
         .get_matches();
 
     let op_path = matches.value_of("op").unwrap_or("op");
     let dest_path = matches.value_of("output").unwrap();
-
-    export(op_path, dest_path)?;
+    let jobs: usize = matches
+        .value_of("jobs")
+        .unwrap()
+        .parse()
+        .map_err(|e| anyhow!("invalid --jobs value: {}", e))?;
+    if jobs == 0 {
+        return Err(anyhow!("--jobs must be at least 1"));
+    }
+    let jobs = jobs.min(MAX_JOBS);
+    let shuffle = matches.is_present("shuffle");
+    let seed = matches
+        .value_of("seed")
+        .map(|s| s.parse())
+        .transpose()
+        .map_err(|e| anyhow!("invalid --seed value: {}", e))?;
+    let filter = ItemFilter {
+        title: matches
+            .value_of("filter")
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| anyhow!("invalid --filter regex: {}", e))?,
+        category: matches.value_of("category").map(|s| s.to_owned()),
+        vault: matches.value_of("vault").map(|s| s.to_owned()),
+    };
+
+    let keep_going = matches.is_present("keep-going");
+    let failures_path = matches.value_of("failures");
+    let reporter_mode: ReporterMode = matches.value_of("reporter").unwrap().parse()?;
+    let sorted = matches.is_present("sorted");
+
+    export(&ExportOptions {
+        op_path,
+        dest_path,
+        jobs,
+        shuffle,
+        seed,
+        filter: &filter,
+        keep_going,
+        failures_path,
+        reporter_mode,
+        sorted,
+    })?;
 
     Ok(())
 }
@@ -330,28 +846,47 @@ mod test {
 
     #[test]
     fn test_fetch_all_items_all_no_items() -> anyhow::Result<()> {
-        let items = super::fetch_all_items(std::sync::Arc::new(super::MockOp {
-            items: std::collections::HashMap::new(),
-        }))?;
+        let result = super::fetch_all_items(
+            std::sync::Arc::new(super::MockOp {
+                items: std::collections::HashMap::new(),
+            }),
+            2,
+            false,
+            None,
+            &super::ItemFilter::default(),
+            false,
+            super::ReporterMode::Pretty,
+        )?;
 
-        assert_eq!(0, items.len());
+        assert_eq!(0, result.items.len());
 
         Ok(())
     }
 
     #[test]
     fn test_fetch_all_items_all_success() -> anyhow::Result<()> {
-        let items = super::fetch_all_items(std::sync::Arc::new(super::MockOp {
-            items: vec![
-                ("id1".to_owned(), Some(json!({"id": "id1"}))),
-                ("id2".to_owned(), Some(json!({"id": "id2"}))),
-            ]
-            .into_iter()
-            .collect(),
-        }))?;
+        let result = super::fetch_all_items(
+            std::sync::Arc::new(super::MockOp {
+                items: vec![
+                    ("id1".to_owned(), Some(json!({"id": "id1"}))),
+                    ("id2".to_owned(), Some(json!({"id": "id2"}))),
+                ]
+                .into_iter()
+                .collect(),
+            }),
+            2,
+            false,
+            None,
+            &super::ItemFilter::default(),
+            false,
+            super::ReporterMode::Pretty,
+        )?;
 
-        let items: std::collections::HashMap<String, super::Item> =
-            items.into_iter().map(|it| (it.id.clone(), it)).collect();
+        let items: std::collections::HashMap<String, super::Item> = result
+            .items
+            .into_iter()
+            .map(|it| (it.id.clone(), it))
+            .collect();
 
         assert_eq!(2, items.len());
         assert_eq!(
@@ -372,17 +907,53 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_fetch_all_items_single_job() -> anyhow::Result<()> {
+        // With only one worker, every id is fetched serially by the same
+        // thread instead of being spread across a pool - exercises `jobs`
+        // actually being threaded down into how many workers get spawned.
+        let result = super::fetch_all_items(
+            std::sync::Arc::new(super::MockOp {
+                items: vec![
+                    ("id1".to_owned(), Some(json!({"id": "id1"}))),
+                    ("id2".to_owned(), Some(json!({"id": "id2"}))),
+                    ("id3".to_owned(), Some(json!({"id": "id3"}))),
+                ]
+                .into_iter()
+                .collect(),
+            }),
+            1,
+            false,
+            None,
+            &super::ItemFilter::default(),
+            false,
+            super::ReporterMode::Pretty,
+        )?;
+
+        assert_eq!(3, result.items.len());
+
+        Ok(())
+    }
+
     #[test]
     fn test_fetch_all_items_some_failed() -> anyhow::Result<()> {
-        let items = super::fetch_all_items(std::sync::Arc::new(super::MockOp {
-            items: vec![
-                ("id1".to_owned(), Some(json!({"id": "id1"}))),
-                ("id2".to_owned(), None),
-                ("id3".to_owned(), Some(json!({"id": "id3"}))),
-            ]
-            .into_iter()
-            .collect(),
-        }));
+        let items = super::fetch_all_items(
+            std::sync::Arc::new(super::MockOp {
+                items: vec![
+                    ("id1".to_owned(), Some(json!({"id": "id1"}))),
+                    ("id2".to_owned(), None),
+                    ("id3".to_owned(), Some(json!({"id": "id3"}))),
+                ]
+                .into_iter()
+                .collect(),
+            }),
+            2,
+            false,
+            None,
+            &super::ItemFilter::default(),
+            false,
+            super::ReporterMode::Pretty,
+        );
 
         match items {
             Ok(_) => Err(anyhow::anyhow!("fetch should have failed")),
@@ -393,6 +964,188 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_fetch_all_items_keep_going_some_failed() -> anyhow::Result<()> {
+        let result = super::fetch_all_items(
+            std::sync::Arc::new(super::MockOp {
+                items: vec![
+                    ("id1".to_owned(), Some(json!({"id": "id1"}))),
+                    ("id2".to_owned(), None),
+                    ("id3".to_owned(), Some(json!({"id": "id3"}))),
+                ]
+                .into_iter()
+                .collect(),
+            }),
+            2,
+            false,
+            None,
+            &super::ItemFilter::default(),
+            true,
+            super::ReporterMode::Pretty,
+        )?;
+
+        assert_eq!(2, result.items.len());
+        assert_eq!(1, result.failures.len());
+        assert_eq!("id2", result.failures[0].0);
+        assert_eq!("mock error for id id2", result.failures[0].1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_streaming_writes_all_items_on_success() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let dest_path = dir.path().join("export.json");
+        let dest_path = dest_path.to_str().unwrap();
+
+        let opts = super::ExportOptions {
+            op_path: "unused",
+            dest_path,
+            jobs: 2,
+            shuffle: false,
+            seed: None,
+            filter: &super::ItemFilter::default(),
+            keep_going: false,
+            failures_path: None,
+            reporter_mode: super::ReporterMode::Pretty,
+            sorted: false,
+        };
+
+        super::export_from(
+            std::sync::Arc::new(super::MockOp {
+                items: vec![
+                    ("id1".to_owned(), Some(json!({"id": "id1"}))),
+                    ("id2".to_owned(), Some(json!({"id": "id2"}))),
+                ]
+                .into_iter()
+                .collect(),
+            }),
+            &opts,
+        )?;
+
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dest_path)?)?;
+        assert_eq!(2, written.as_array().unwrap().len());
+        assert!(!std::path::Path::new(&format!("{}.tmp", dest_path)).exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_streaming_leaves_destination_untouched_on_failure() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let dest_path = dir.path().join("export.json");
+        let dest_path = dest_path.to_str().unwrap();
+
+        let opts = super::ExportOptions {
+            op_path: "unused",
+            dest_path,
+            jobs: 2,
+            shuffle: false,
+            seed: None,
+            filter: &super::ItemFilter::default(),
+            keep_going: false,
+            failures_path: None,
+            reporter_mode: super::ReporterMode::Pretty,
+            sorted: false,
+        };
+
+        let result = super::export_from(
+            std::sync::Arc::new(super::MockOp {
+                items: vec![
+                    ("id1".to_owned(), Some(json!({"id": "id1"}))),
+                    ("id2".to_owned(), None),
+                ]
+                .into_iter()
+                .collect(),
+            }),
+            &opts,
+        );
+
+        assert!(result.is_err());
+        assert!(!std::path::Path::new(dest_path).exists());
+        assert!(!std::path::Path::new(&format!("{}.tmp", dest_path)).exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_item_filter_no_filters_matches_everything() {
+        assert!(super::ItemFilter::default().matches(&json!({})));
+    }
+
+    #[test]
+    fn test_item_filter_title_matches_regex() -> anyhow::Result<()> {
+        let filter = super::ItemFilter {
+            title: Some(regex::Regex::new("^Bank")?),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&json!({"title": "Bank Account"})));
+        assert!(!filter.matches(&json!({"title": "Personal Email"})));
+        assert!(!filter.matches(&json!({})));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_item_filter_category() {
+        let filter = super::ItemFilter {
+            category: Some("LOGIN".to_owned()),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&json!({"category": "LOGIN"})));
+        assert!(!filter.matches(&json!({"category": "PASSWORD"})));
+        assert!(!filter.matches(&json!({})));
+    }
+
+    #[test]
+    fn test_item_filter_vault_matches_object_id_or_name() {
+        let filter = super::ItemFilter {
+            vault: Some("Personal".to_owned()),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&json!({"vault": {"id": "Personal", "name": "Other"}})));
+        assert!(filter.matches(&json!({"vault": {"id": "v1", "name": "Personal"}})));
+        assert!(!filter.matches(&json!({"vault": {"id": "v1", "name": "Other"}})));
+    }
+
+    #[test]
+    fn test_item_filter_vault_matches_string() {
+        let filter = super::ItemFilter {
+            vault: Some("Personal".to_owned()),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&json!({"vault": "Personal"})));
+        assert!(!filter.matches(&json!({"vault": "Other"})));
+        assert!(!filter.matches(&json!({"vault": 42})));
+    }
+
+    #[test]
+    fn test_progress_reporter_tracks_pending_count_in_json_mode() {
+        // Doesn't assert on the JSON actually printed to stderr, but does
+        // exercise the `ReporterMode::Json` branches of `fetched`/`done`
+        // (rather than only ever running them in `Pretty` mode, as
+        // the `fetch_all_items` tests do) and checks the bookkeeping they
+        // share with `Pretty` mode is correct.
+        let mut progress = super::ProgressReporter::new(super::ReporterMode::Json);
+
+        progress.pending();
+        progress.pending();
+        assert_eq!(2, progress.num_pending);
+
+        progress.fetched("id1");
+        assert_eq!(1, progress.num_pending);
+
+        progress.fetched("id2");
+        assert_eq!(0, progress.num_pending);
+
+        progress.done(2, 0);
+    }
+
     struct MockTool {
         path: tempfile::TempPath,
     }
@@ -441,7 +1194,7 @@ mod test {
 
         let items = op.list_items().unwrap();
         assert_eq!(1, items.len());
-        assert_eq!("value", items.get(0).unwrap());
+        assert_eq!("value", items.get(0).unwrap()["id"]);
 
         Ok(())
     }
@@ -453,8 +1206,8 @@ mod test {
 
         let items = op.list_items().unwrap();
         assert_eq!(2, items.len());
-        assert_eq!("value1", items.get(0).unwrap());
-        assert_eq!("value2", items.get(1).unwrap());
+        assert_eq!("value1", items.get(0).unwrap()["id"]);
+        assert_eq!("value2", items.get(1).unwrap()["id"]);
 
         Ok(())
     }
@@ -499,6 +1252,29 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_tool_op_get_item_retries_until_success() -> anyhow::Result<()> {
+        // Exercises the decorrelated-jitter backoff loop actually retrying
+        // (rather than giving up after the first failure) and succeeding
+        // once the underlying command does, while staying within the
+        // 5-try limit.
+        let counter = tempfile::NamedTempFile::new()?;
+        let counter_path = counter.path().to_str().unwrap().to_owned();
+        std::fs::write(&counter_path, b"0")?;
+
+        let script = format!(
+            "#!/bin/bash\ncount=$(<\"{0}\")\ncount=$((count + 1))\necho $count > \"{0}\"\n[ $count -lt 3 ] && exit 1\necho '{{\"key\": \"value\"}}'",
+            counter_path
+        );
+        let (op, _tool) = optool(script.as_bytes());
+
+        let item = op.get_item("id")?;
+        assert_eq!(serde_json::json!({"key": "value"}), item);
+        assert_eq!("3", std::fs::read_to_string(&counter_path)?.trim());
+
+        Ok(())
+    }
+
     #[test]
     fn test_tool_op_get_item_correct_arguments() -> anyhow::Result<()> {
         let (op, _tool) = optool(b"#!/bin/bash\n [[ \"$1\" == \"items\" ]] && [[ \"$2\" == \"get\" ]] && [[ \"$3\" == \"--format=json\" ]] && [[ \"$4\" == \"id\" ]] && [[ \"$5\" == \"\" ]] && echo {}");